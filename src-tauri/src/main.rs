@@ -7,9 +7,137 @@ struct GenerateArgs {
     prompt: String,
     model: String,
     temperature: f32,
+    #[serde(default)]
+    on_token: Option<tauri::ipc::Channel<String>>,
+    #[serde(default)]
+    provider: Provider,
+    /// Overrides `OLLAMA_BASE_URL` / the provider's default base URL.
+    #[serde(default)]
+    base_url: Option<String>,
+    /// Name of the environment variable holding the `Authorization: Bearer` token.
+    #[serde(default)]
+    api_key_env: Option<String>,
+    #[serde(default)]
+    http: HttpConfig,
 }
 
-#[derive(Debug, Serialize)]
+/// Backend a generation request targets. Each variant knows its default base
+/// URL, how to build its request body, and how to parse its response shape.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Provider {
+    Ollama,
+    OpenAiCompatible,
+}
+
+impl Default for Provider {
+    fn default() -> Self {
+        Provider::Ollama
+    }
+}
+
+impl Provider {
+    fn default_base_url(&self) -> &'static str {
+        match self {
+            Provider::Ollama => "http://127.0.0.1:11434",
+            Provider::OpenAiCompatible => "https://api.openai.com",
+        }
+    }
+
+    fn request_url(&self, base: &str) -> String {
+        match self {
+            Provider::Ollama => format!("{base}/api/generate"),
+            Provider::OpenAiCompatible => format!("{base}/v1/chat/completions"),
+        }
+    }
+
+    fn build_body(&self, model: &str, prompt: &str, temperature: f32) -> serde_json::Value {
+        let full_prompt = format!("{}\n\nUSER PROMPT:\n{}", SYSTEM_INSTRUCTIONS, prompt);
+        match self {
+            Provider::Ollama => serde_json::json!({
+                "model": model,
+                "prompt": full_prompt,
+                "stream": true,
+                "options": { "temperature": temperature }
+            }),
+            Provider::OpenAiCompatible => serde_json::json!({
+                "model": model,
+                "messages": [{ "role": "user", "content": full_prompt }],
+                "temperature": temperature,
+                "stream": false
+            }),
+        }
+    }
+}
+
+/// HTTP tuning knobs so flaky local servers and hosted APIs are both handled
+/// reliably. Defaults: 10s connect timeout, 120s overall timeout, redirects
+/// not followed, 2 retries.
+#[derive(Debug, Deserialize)]
+struct HttpConfig {
+    #[serde(default = "HttpConfig::default_connect_timeout_secs")]
+    connect_timeout_secs: u64,
+    #[serde(default = "HttpConfig::default_total_timeout_secs")]
+    total_timeout_secs: u64,
+    #[serde(default)]
+    follow_redirects: bool,
+    #[serde(default = "HttpConfig::default_retries")]
+    retries: u32,
+}
+
+impl HttpConfig {
+    fn default_connect_timeout_secs() -> u64 {
+        10
+    }
+    fn default_total_timeout_secs() -> u64 {
+        120
+    }
+    fn default_retries() -> u32 {
+        2
+    }
+
+    fn build_client(&self) -> Result<reqwest::Client, String> {
+        let policy = if self.follow_redirects {
+            reqwest::redirect::Policy::limited(10)
+        } else {
+            reqwest::redirect::Policy::none()
+        };
+        reqwest::Client::builder()
+            .connect_timeout(std::time::Duration::from_secs(self.connect_timeout_secs))
+            .timeout(std::time::Duration::from_secs(self.total_timeout_secs))
+            .redirect(policy)
+            .build()
+            .map_err(|e| format!("failed to build HTTP client: {e}"))
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            connect_timeout_secs: Self::default_connect_timeout_secs(),
+            total_timeout_secs: Self::default_total_timeout_secs(),
+            follow_redirects: false,
+            retries: Self::default_retries(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiResp {
+    choices: Vec<OpenAiChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiChoice {
+    message: OpenAiMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiMessage {
+    content: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
 struct Triple {
     html: String,
     css: String,
@@ -17,8 +145,10 @@ struct Triple {
 }
 
 #[derive(Debug, Deserialize)]
-struct OllamaResp {
+struct OllamaStreamChunk {
     response: String,
+    #[serde(default)]
+    done: bool,
 }
 
 const SYSTEM_INSTRUCTIONS: &str = r#"
@@ -31,55 +161,548 @@ Return ONLY a JSON object with EXACT keys: "html", "css", "js". No commentary.
 Constraints: mobile-first, responsive, no external CDNs, self-contained, no inline events; use addEventListener.
 "#;
 
+/// Strips ```` ``` ```` / ```` ```json ```` fences so a fenced code block
+/// doesn't get scanned as part of the surrounding prose.
+fn strip_code_fences(s: &str) -> &str {
+    let s = s.trim();
+    let Some(rest) = s.strip_prefix("```") else {
+        return s;
+    };
+    let rest = rest.strip_prefix("json").unwrap_or(rest);
+    let rest = rest.trim_start_matches(['\n', '\r']);
+    match rest.rfind("```") {
+        Some(end) => rest[..end].trim(),
+        None => rest.trim(),
+    }
+}
+
+/// Scans for the first balanced top-level `{...}` object, tracking brace
+/// depth while skipping over string literals (so braces inside HTML/CSS/JS
+/// string values don't confuse the matcher).
+fn find_balanced_object(s: &str) -> Option<&str> {
+    let bytes = s.as_bytes();
+    let start = s.find('{')?;
+
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, &b) in bytes.iter().enumerate().skip(start) {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match b {
+            b'"' => in_string = true,
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&s[start..=i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Pulls a single `"key": "value"` string field out of raw text, decoding
+/// standard JSON escapes (`\n`, `\t`, `\r`, `\"`, `\\`, `\/`, `\uXXXX`), for
+/// use as a fallback when the object as a whole doesn't parse.
+fn scan_key(s: &str, key: &str) -> Option<String> {
+    let needle = format!("\"{key}\"");
+    let key_pos = s.find(&needle)?;
+    let after_key = &s[key_pos + needle.len()..];
+    let colon_pos = after_key.find(':')?;
+    let after_colon = after_key[colon_pos + 1..].trim_start();
+    let value = after_colon.strip_prefix('"')?;
+
+    let mut out = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            if c == '"' {
+                return Some(out);
+            }
+            out.push(c);
+            continue;
+        }
+
+        match chars.next()? {
+            'n' => out.push('\n'),
+            't' => out.push('\t'),
+            'r' => out.push('\r'),
+            'b' => out.push('\u{8}'),
+            'f' => out.push('\u{c}'),
+            '"' => out.push('"'),
+            '\\' => out.push('\\'),
+            '/' => out.push('/'),
+            'u' => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let code = u32::from_str_radix(&hex, 16).ok()?;
+                out.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+            }
+            other => out.push(other),
+        }
+    }
+    None
+}
+
 fn extract_json_triple(s: &str) -> anyhow::Result<Triple> {
-    let start = s.find('{').ok_or_else(|| anyhow::anyhow!("No JSON start"))?;
-    let end = s.rfind('}').ok_or_else(|| anyhow::anyhow!("No JSON end"))?;
-    let slice = &s[start..=end];
-    let v: serde_json::Value = serde_json::from_str(slice)?;
-    let html = v.get("html").and_then(|x| x.as_str()).ok_or_else(|| anyhow::anyhow!("missing html"))?.to_string();
-    let css = v.get("css").and_then(|x| x.as_str()).ok_or_else(|| anyhow::anyhow!("missing css"))?.to_string();
-    let js  = v.get("js").and_then(|x| x.as_str()).ok_or_else(|| anyhow::anyhow!("missing js"))?.to_string();
-    Ok(Triple { html, css, js })
+    let stripped = strip_code_fences(s);
+
+    if let Some(slice) = find_balanced_object(stripped) {
+        if let Ok(v) = serde_json::from_str::<serde_json::Value>(slice) {
+            let html = v.get("html").and_then(|x| x.as_str()).map(str::to_string);
+            let css = v.get("css").and_then(|x| x.as_str()).map(str::to_string);
+            let js = v.get("js").and_then(|x| x.as_str()).map(str::to_string);
+            if let (Some(html), Some(css), Some(js)) = (&html, &css, &js) {
+                return Ok(Triple { html: html.clone(), css: css.clone(), js: js.clone() });
+            }
+        }
+    }
+
+    // Fall back to scanning for each key individually so a malformed or
+    // truncated object still parses when all three fields are present;
+    // otherwise the error names the missing keys.
+    let html = scan_key(stripped, "html");
+    let css = scan_key(stripped, "css");
+    let js = scan_key(stripped, "js");
+
+    match (html, css, js) {
+        (Some(html), Some(css), Some(js)) => Ok(Triple { html, css, js }),
+        (html, css, js) => {
+            let mut missing = Vec::new();
+            if html.is_none() {
+                missing.push("html");
+            }
+            if css.is_none() {
+                missing.push("css");
+            }
+            if js.is_none() {
+                missing.push("js");
+            }
+            anyhow::bail!("missing keys: {}", missing.join(", "));
+        }
+    }
 }
 
+/// Holds the most recently generated site so the `slate://preview` protocol
+/// handler can serve it without writing anything to disk.
+struct PreviewState(std::sync::Mutex<Option<Triple>>);
+
 #[tauri::command]
-async fn generate_site(args: GenerateArgs) -> Result<Triple, String> {
-    let base = std::env::var("OLLAMA_BASE_URL").unwrap_or_else(|_| "http://127.0.0.1:11434".into());
-
-    let body = serde_json::json!({
-        "model": args.model,
-        "prompt": format!("{}\n\nUSER PROMPT:\n{}", SYSTEM_INSTRUCTIONS, args.prompt),
-        "stream": false,
-        "options": { "temperature": args.temperature }
-    });
-
-    let client = reqwest::Client::new();
-    let res = client
-        .post(format!("{}/api/generate", base))
-        .json(&body)
-        .send()
-        .await
-        .map_err(|e| format!("Ollama request failed: {e}"))?;
-
-    if !res.status().is_success() {
-        return Err(format!("Ollama error status: {}", res.status()));
-    }
-
-    let data: OllamaResp = res.json().await.map_err(|e| format!("Invalid JSON: {e}"))?;
-    let triple = extract_json_triple(&data.response).map_err(|e| format!("Parse error: {e}"))?;
+async fn generate_site(app: AppHandle, args: GenerateArgs) -> Result<Triple, String> {
+    let base = args
+        .base_url
+        .clone()
+        .or_else(|| std::env::var("OLLAMA_BASE_URL").ok())
+        .unwrap_or_else(|| args.provider.default_base_url().to_string());
+
+    let api_key = args
+        .api_key_env
+        .as_deref()
+        .and_then(|var| std::env::var(var).ok());
+
+    let client = args.http.build_client()?;
+    let url = args.provider.request_url(&base);
+    let body = args.provider.build_body(&args.model, &args.prompt, args.temperature);
+
+    let res = send_with_retry(&client, &url, &body, api_key.as_deref(), args.http.retries).await?;
+
+    let accumulated = match args.provider {
+        Provider::Ollama => stream_ollama_response(res, &args.on_token).await?,
+        Provider::OpenAiCompatible => {
+            let data: OpenAiResp = res.json().await.map_err(|e| format!("Invalid JSON: {e}"))?;
+            let content = data
+                .choices
+                .into_iter()
+                .next()
+                .ok_or_else(|| "no choices in response".to_string())?
+                .message
+                .content;
+            if let Some(channel) = &args.on_token {
+                let _ = channel.send(content.clone());
+            }
+            content
+        }
+    };
+
+    let triple = extract_json_triple(&accumulated).map_err(|e| format!("Parse error: {e}"))?;
+
+    let state = app.state::<PreviewState>();
+    *state.0.lock().unwrap() = Some(triple.clone());
+
     Ok(triple)
 }
 
-use tauri::AppHandle;
+/// Caps how many times a request is retried; also keeps `2u64.pow(attempt)`
+/// in the backoff delay below well clear of overflow.
+const MAX_RETRIES: u32 = 10;
+
+/// Sends the request, retrying with exponential backoff on transport errors
+/// and 5xx responses so a flaky local server doesn't fail the whole generation.
+async fn send_with_retry(
+    client: &reqwest::Client,
+    url: &str,
+    body: &serde_json::Value,
+    api_key: Option<&str>,
+    retries: u32,
+) -> Result<reqwest::Response, String> {
+    let retries = retries.min(MAX_RETRIES);
+    let mut attempt = 0;
+    loop {
+        let mut req = client.post(url).json(body);
+        if let Some(key) = api_key {
+            req = req.header("Authorization", format!("Bearer {key}"));
+        }
+
+        match req.send().await {
+            Ok(res) if res.status().is_success() => return Ok(res),
+            Ok(res) if attempt < retries && res.status().is_server_error() => {
+                attempt += 1;
+                tokio::time::sleep(std::time::Duration::from_millis(200 * 2u64.pow(attempt))).await;
+            }
+            Ok(res) => return Err(format!("request failed with status: {}", res.status())),
+            Err(_) if attempt < retries => {
+                attempt += 1;
+                tokio::time::sleep(std::time::Duration::from_millis(200 * 2u64.pow(attempt))).await;
+            }
+            Err(e) => return Err(format!("request failed: {e}")),
+        }
+    }
+}
+
+/// Ollama streams newline-delimited JSON objects; buffer partial lines
+/// across chunks and forward each fragment over `on_token` as it arrives.
+async fn stream_ollama_response(
+    res: reqwest::Response,
+    on_token: &Option<tauri::ipc::Channel<String>>,
+) -> Result<String, String> {
+    use futures_util::StreamExt;
+
+    let mut stream = res.bytes_stream();
+    let mut buf: Vec<u8> = Vec::new();
+    let mut accumulated = String::new();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Stream read failed: {e}"))?;
+        buf.extend_from_slice(&chunk);
+
+        while let Some(pos) = buf.iter().position(|&b| b == b'\n') {
+            let line_bytes: Vec<u8> = buf.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line_bytes).trim().to_string();
+            if line.is_empty() {
+                continue;
+            }
+            let parsed: OllamaStreamChunk =
+                serde_json::from_str(&line).map_err(|e| format!("Invalid JSON: {e}"))?;
+            accumulated.push_str(&parsed.response);
+            if let Some(channel) = on_token {
+                let _ = channel.send(parsed.response);
+            }
+            if parsed.done {
+                // Don't wait on the stream to close: some servers/proxies keep
+                // the connection open after the final chunk.
+                return Ok(accumulated);
+            }
+        }
+    }
+
+    let remainder = String::from_utf8_lossy(&buf);
+    let remainder = remainder.trim();
+    if !remainder.is_empty() {
+        let parsed: OllamaStreamChunk =
+            serde_json::from_str(remainder).map_err(|e| format!("Invalid JSON: {e}"))?;
+        accumulated.push_str(&parsed.response);
+        if let Some(channel) = on_token {
+            let _ = channel.send(parsed.response);
+        }
+    }
+
+    Ok(accumulated)
+}
+
+/// Returns the `slate://` URL the frontend can load in an `<iframe>` or
+/// child webview to live-preview the most recently generated site.
+#[tauri::command]
+fn preview_site() -> String {
+    "slate://preview/index.html".to_string()
+}
+
+/// Generic over the Tauri runtime so it can be exercised against
+/// `tauri::test::MockRuntime` in tests as well as the real `Wry` runtime.
+fn preview_protocol_handler<R: tauri::Runtime>(
+    app: &AppHandle<R>,
+    request: tauri::http::Request<Vec<u8>>,
+) -> tauri::http::Response<Vec<u8>> {
+    let state = app.state::<PreviewState>();
+    let triple = state.0.lock().unwrap().clone();
+
+    let Some(triple) = triple else {
+        return tauri::http::Response::builder()
+            .status(tauri::http::StatusCode::NOT_FOUND)
+            .header("Content-Type", "text/plain")
+            .body(b"no site generated yet".to_vec())
+            .unwrap();
+    };
+
+    let path = request.uri().path();
+    let (body, content_type): (Vec<u8>, &str) = match path {
+        "/style.css" => (triple.css.into_bytes(), "text/css"),
+        "/app.js" => (triple.js.into_bytes(), "application/javascript"),
+        _ => {
+            let html = stitch_preview_html(&triple.html);
+            (html.into_bytes(), "text/html")
+        }
+    };
+
+    tauri::http::Response::builder()
+        .status(tauri::http::StatusCode::OK)
+        .header("Content-Type", content_type)
+        .body(body)
+        .unwrap()
+}
+
+/// Injects `<link>`/`<script>` tags pointing at the sibling `/style.css` and
+/// `/app.js` resources so the generated HTML loads like a real deployed site.
+fn stitch_preview_html(html: &str) -> String {
+    let injected = "<link rel=\"stylesheet\" href=\"/style.css\"><script src=\"/app.js\" defer></script>";
+    if let Some(pos) = html.find("</head>") {
+        let mut out = String::with_capacity(html.len() + injected.len());
+        out.push_str(&html[..pos]);
+        out.push_str(injected);
+        out.push_str(&html[pos..]);
+        out
+    } else {
+        format!("<head>{injected}</head>{html}")
+    }
+}
+
+use tauri::{AppHandle, Manager};
 use tauri_plugin_dialog::{DialogExt, FilePath};
 use std::path::PathBuf;
 
+/// Builds one self-contained `index.html`: CSS inlined in a `<style>` tag,
+/// JS inlined in a `<script>` tag, remote `<link rel="stylesheet" href>`
+/// tags replaced with a `<style>` tag holding the fetched CSS, and remote
+/// `src="..."` and `url(...)` references (including those in fetched
+/// stylesheets) replaced with `data:` URIs, mirroring how a page-archiver
+/// saves a page. A `src`/`url(...)` reference whose fetch fails is left as it
+/// is; a stylesheet that fails to fetch fails the export.
+async fn build_monolith(triple: &Triple) -> Result<String, String> {
+    let mut css = triple.css.clone();
+    inline_remote_urls(&mut css).await?;
+
+    let mut html = triple.html.clone();
+    inline_stylesheet_links(&mut html).await?;
+    inline_remote_urls(&mut html).await?;
+
+    let style_tag = format!("<style>{css}</style>");
+    let script_tag = format!("<script>{}</script>", triple.js);
+
+    if let Some(pos) = html.find("</head>") {
+        html.insert_str(pos, &style_tag);
+    } else {
+        html = format!("<head>{style_tag}</head>{html}");
+    }
+
+    if let Some(pos) = html.rfind("</body>") {
+        html.insert_str(pos, &script_tag);
+    } else {
+        html.push_str(&script_tag);
+    }
+
+    Ok(html)
+}
+
+/// Replaces every `url(...)` in CSS and `src="..."` reference in HTML that
+/// points at an `http(s)://` resource with a base64 `data:` URI. References
+/// whose fetch fails are left unchanged.
+async fn inline_remote_urls(s: &mut String) -> Result<(), String> {
+    for pattern in ["url(", "src=\""] {
+        let mut search_from = 0;
+        while let Some(rel) = s[search_from..].find(pattern) {
+            let start = search_from + rel + pattern.len();
+            let quote = if pattern == "url(" { None } else { Some('"') };
+            let end = match quote {
+                Some(q) => s[start..].find(q).map(|p| start + p),
+                None => s[start..].find(')').map(|p| start + p),
+            };
+            let Some(end) = end else { break };
+            let url = s[start..end].trim_matches('\'').trim_matches('"').to_string();
+
+            if url.starts_with("http://") || url.starts_with("https://") {
+                if let Ok(data_uri) = fetch_as_data_uri(&url).await {
+                    s.replace_range(start..end, &data_uri);
+                    search_from = start + data_uri.len();
+                    continue;
+                }
+            }
+            search_from = end;
+        }
+    }
+    Ok(())
+}
+
+/// Replaces `<link rel="stylesheet" href="...">` tags pointing at a remote
+/// URL with an inlined `<style>` tag containing the fetched CSS.
+async fn inline_stylesheet_links(html: &mut String) -> Result<(), String> {
+    let mut search_from = 0;
+    loop {
+        let Some(tag_start_rel) = html[search_from..].find("<link") else { break };
+        let tag_start = search_from + tag_start_rel;
+        let Some(tag_end_rel) = html[tag_start..].find('>') else { break };
+        let tag_end = tag_start + tag_end_rel + 1;
+        let tag = html[tag_start..tag_end].to_string();
+
+        if !tag.contains("stylesheet") {
+            search_from = tag_end;
+            continue;
+        }
+        let Some(href_start) = tag.find("href=\"") else {
+            search_from = tag_end;
+            continue;
+        };
+        let href_start = href_start + "href=\"".len();
+        let Some(href_end) = tag[href_start..].find('"') else {
+            search_from = tag_end;
+            continue;
+        };
+        let href = &tag[href_start..href_start + href_end];
+
+        if !(href.starts_with("http://") || href.starts_with("https://")) {
+            search_from = tag_end;
+            continue;
+        }
+
+        let css = reqwest::get(href)
+            .await
+            .map_err(|e| format!("failed to fetch stylesheet {href}: {e}"))?
+            .text()
+            .await
+            .map_err(|e| format!("failed to read stylesheet {href}: {e}"))?;
+
+        let style_tag = format!("<style>{css}</style>");
+        let new_search_from = tag_start + style_tag.len();
+        html.replace_range(tag_start..tag_end, &style_tag);
+        search_from = new_search_from;
+    }
+    Ok(())
+}
+
+async fn fetch_as_data_uri(url: &str) -> Result<String, String> {
+    let res = reqwest::get(url).await.map_err(|e| e.to_string())?;
+    let mime = res
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| guess_mime_from_extension(url));
+    let bytes = res.bytes().await.map_err(|e| e.to_string())?;
+    let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &bytes);
+    Ok(format!("data:{mime};base64,{encoded}"))
+}
+
+fn guess_mime_from_extension(url: &str) -> String {
+    let ext = url.rsplit('.').next().unwrap_or("").to_lowercase();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "woff2" => "font/woff2",
+        "woff" => "font/woff",
+        "css" => "text/css",
+        _ => "application/octet-stream",
+    }
+    .to_string()
+}
+
+/// Produces a portable single-file export and reuses the `save_zip`
+/// file-dialog flow to let the user choose where to save it.
+#[tauri::command]
+async fn export_single_file(app: AppHandle, triple: Triple, default_name: Option<String>) -> Result<SaveResult, String> {
+    let html = build_monolith(&triple).await?;
+    save_zip(
+        app,
+        Some(default_name.unwrap_or_else(|| "index.html".to_string())),
+        html.into_bytes(),
+        None,
+    )
+    .await
+}
+
+/// Compression used for the companion file written next to the exported
+/// bundle; the bundle itself is always written uncompressed.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Compression {
+    None,
+    Gzip,
+    Brotli,
+    Zstd,
+}
+
+impl Compression {
+    /// Extension appended to the companion pre-compressed file written
+    /// alongside the uncompressed asset, as static hosts expect.
+    fn companion_extension(&self) -> &'static str {
+        match self {
+            Compression::None => "",
+            Compression::Gzip => "gz",
+            Compression::Brotli => "br",
+            Compression::Zstd => "zst",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SaveResult {
+    path: String,
+    original_size: usize,
+    compressed_size: usize,
+}
+
+fn compress_bytes(bytes: &[u8], compression: Compression) -> Result<Vec<u8>, String> {
+    use std::io::Write;
+
+    match compression {
+        Compression::None => Ok(bytes.to_vec()),
+        Compression::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(bytes).map_err(|e| format!("gzip failed: {e}"))?;
+            encoder.finish().map_err(|e| format!("gzip failed: {e}"))
+        }
+        Compression::Brotli => {
+            let mut out = Vec::new();
+            let mut writer = brotli::CompressorWriter::new(&mut out, 4096, 11, 22);
+            writer.write_all(bytes).map_err(|e| format!("brotli failed: {e}"))?;
+            drop(writer);
+            Ok(out)
+        }
+        Compression::Zstd => zstd::encode_all(bytes, 0).map_err(|e| format!("zstd failed: {e}")),
+    }
+}
+
 #[tauri::command]
 async fn save_zip(
   app: AppHandle,
   default_name: Option<String>,
   bytes: Vec<u8>,
-) -> Result<String, String> {
+  compression: Option<Compression>,
+) -> Result<SaveResult, String> {
+  let compression = compression.unwrap_or(Compression::None);
+
   let picked = app
     .dialog()
     .file()
@@ -100,14 +723,228 @@ async fn save_zip(
 
   std::fs::write(&pb, &bytes).map_err(|e| format!("write failed: {e}"))?;
 
-  Ok(pb.to_string_lossy().into_owned())
+  let compressed_size = if compression == Compression::None {
+    bytes.len()
+  } else {
+    let compress_input = bytes.clone();
+    let compressed = tokio::task::spawn_blocking(move || compress_bytes(&compress_input, compression))
+      .await
+      .map_err(|e| format!("compression task failed: {e}"))??;
+    let companion_path = format!("{}.{}", pb.display(), compression.companion_extension());
+    std::fs::write(&companion_path, &compressed).map_err(|e| format!("write failed: {e}"))?;
+    compressed.len()
+  };
+
+  Ok(SaveResult {
+    path: pb.to_string_lossy().into_owned(),
+    original_size: bytes.len(),
+    compressed_size,
+  })
 }
 
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stitch_preview_html_injects_before_closing_head() {
+        let html = "<html><head><title>t</title></head><body></body></html>";
+        let stitched = stitch_preview_html(html);
+        assert!(stitched.contains("<link rel=\"stylesheet\" href=\"/style.css\">"));
+        assert!(stitched.contains("<script src=\"/app.js\" defer></script>"));
+        assert!(stitched.find("</title>").unwrap() < stitched.find("<link").unwrap());
+        assert!(stitched.find("<link").unwrap() < stitched.find("</head>").unwrap());
+    }
+
+    #[test]
+    fn stitch_preview_html_handles_missing_head_tag() {
+        let html = "<body>no head here</body>";
+        let stitched = stitch_preview_html(html);
+        assert!(stitched.starts_with("<head>"));
+        assert!(stitched.contains("<link rel=\"stylesheet\" href=\"/style.css\">"));
+        assert!(stitched.contains("no head here"));
+    }
+
+    fn mock_triple() -> Triple {
+        Triple {
+            html: "<html><head></head><body>hi</body></html>".to_string(),
+            css: "body{color:red}".to_string(),
+            js: "console.log('hi')".to_string(),
+        }
+    }
+
+    fn mock_app_with_preview(triple: Option<Triple>) -> tauri::App<tauri::test::MockRuntime> {
+        tauri::test::mock_builder()
+            .manage(PreviewState(std::sync::Mutex::new(triple)))
+            .build(tauri::generate_context!())
+            .expect("failed to build mock app")
+    }
+
+    fn preview_request(path: &str) -> tauri::http::Request<Vec<u8>> {
+        tauri::http::Request::builder()
+            .uri(format!("slate://preview{path}"))
+            .body(Vec::new())
+            .unwrap()
+    }
+
+    #[test]
+    fn preview_protocol_handler_returns_not_found_with_no_site_generated() {
+        let app = mock_app_with_preview(None);
+        let res = preview_protocol_handler(&app.handle().clone(), preview_request("/index.html"));
+        assert_eq!(res.status(), tauri::http::StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn preview_protocol_handler_serves_stylesheet_and_script_by_path() {
+        let app = mock_app_with_preview(Some(mock_triple()));
+        let handle = app.handle().clone();
+
+        let css_res = preview_protocol_handler(&handle, preview_request("/style.css"));
+        assert_eq!(css_res.status(), tauri::http::StatusCode::OK);
+        assert_eq!(css_res.headers().get("Content-Type").unwrap(), "text/css");
+        assert_eq!(css_res.body(), &mock_triple().css.into_bytes());
+
+        let js_res = preview_protocol_handler(&handle, preview_request("/app.js"));
+        assert_eq!(js_res.status(), tauri::http::StatusCode::OK);
+        assert_eq!(js_res.headers().get("Content-Type").unwrap(), "application/javascript");
+        assert_eq!(js_res.body(), &mock_triple().js.into_bytes());
+    }
+
+    #[test]
+    fn preview_protocol_handler_serves_stitched_html_for_other_paths() {
+        let app = mock_app_with_preview(Some(mock_triple()));
+        let res = preview_protocol_handler(&app.handle().clone(), preview_request("/index.html"));
+        assert_eq!(res.status(), tauri::http::StatusCode::OK);
+        assert_eq!(res.headers().get("Content-Type").unwrap(), "text/html");
+        let body = String::from_utf8(res.body().clone()).unwrap();
+        assert!(body.contains("/style.css"));
+        assert!(body.contains("/app.js"));
+    }
+
+    #[test]
+    fn strip_code_fences_removes_json_fence() {
+        let input = "```json\n{\"html\":\"\"}\n```";
+        assert_eq!(strip_code_fences(input), "{\"html\":\"\"}");
+    }
+
+    #[test]
+    fn strip_code_fences_removes_plain_fence() {
+        let input = "```\n{\"html\":\"\"}\n```";
+        assert_eq!(strip_code_fences(input), "{\"html\":\"\"}");
+    }
+
+    #[test]
+    fn strip_code_fences_passes_through_unfenced_text() {
+        assert_eq!(strip_code_fences("{\"html\":\"\"}"), "{\"html\":\"\"}");
+    }
+
+    #[test]
+    fn find_balanced_object_skips_nested_braces_in_strings() {
+        let input = r#"prose {"html": "<div style=\"{color:red}\"></div>"} trailing"#;
+        let found = find_balanced_object(input).unwrap();
+        assert_eq!(found, r#"{"html": "<div style=\"{color:red}\"></div>"}"#);
+    }
+
+    #[test]
+    fn find_balanced_object_stops_at_first_top_level_object() {
+        let input = r#"{"a": {"nested": true}} {"b": 1}"#;
+        assert_eq!(find_balanced_object(input).unwrap(), r#"{"a": {"nested": true}}"#);
+    }
+
+    #[test]
+    fn scan_key_decodes_standard_json_escapes() {
+        let input = r#""js": "console.log(1);\nconsole.log(2);\ttabbed\nend""#;
+        assert_eq!(
+            scan_key(input, "js").unwrap(),
+            "console.log(1);\nconsole.log(2);\ttabbed\nend"
+        );
+    }
+
+    #[test]
+    fn scan_key_decodes_unicode_escapes() {
+        let input = "\"css\": \"content: \\\"\\u2603\\\"\"";
+        assert_eq!(scan_key(input, "css").unwrap(), "content: \"\u{2603}\"");
+    }
+
+    #[test]
+    fn scan_key_returns_none_when_key_missing() {
+        assert_eq!(scan_key(r#""html": "ok""#, "css"), None);
+    }
+
+    #[test]
+    fn extract_json_triple_falls_back_to_key_scan_on_malformed_object() {
+        let input = r#"Sure! ```json
+{"html": "<div></div>", "css": "body{color:red}", "js": "a();\nb();"
+"#;
+        let triple = extract_json_triple(input).unwrap();
+        assert_eq!(triple.html, "<div></div>");
+        assert_eq!(triple.css, "body{color:red}");
+        assert_eq!(triple.js, "a();\nb();");
+    }
+
+    #[test]
+    fn extract_json_triple_reports_missing_keys() {
+        let err = extract_json_triple(r#"{"html": "<div></div>"}"#).unwrap_err();
+        assert!(err.to_string().contains("css"));
+        assert!(err.to_string().contains("js"));
+    }
+
+    #[test]
+    fn compress_bytes_none_returns_input_unchanged() {
+        let input = b"hello world";
+        assert_eq!(compress_bytes(input, Compression::None).unwrap(), input);
+    }
+
+    #[test]
+    fn compress_bytes_gzip_round_trips() {
+        let input = b"hello world, this is some sample site content";
+        let compressed = compress_bytes(input, Compression::Gzip).unwrap();
+        assert_ne!(compressed, input);
+
+        let mut decoder = flate2::read::GzDecoder::new(&compressed[..]);
+        let mut decoded = Vec::new();
+        std::io::Read::read_to_end(&mut decoder, &mut decoded).unwrap();
+        assert_eq!(decoded, input);
+    }
+
+    #[test]
+    fn compress_bytes_brotli_round_trips() {
+        let input = b"hello world, this is some sample site content";
+        let compressed = compress_bytes(input, Compression::Brotli).unwrap();
+        assert_ne!(compressed, input);
+
+        let mut decoded = Vec::new();
+        brotli::BrotliDecompress(&mut &compressed[..], &mut decoded).unwrap();
+        assert_eq!(decoded, input);
+    }
+
+    #[test]
+    fn compress_bytes_zstd_round_trips() {
+        let input = b"hello world, this is some sample site content";
+        let compressed = compress_bytes(input, Compression::Zstd).unwrap();
+        assert_ne!(compressed, input);
+        assert_eq!(zstd::decode_all(&compressed[..]).unwrap(), input);
+    }
+
+    #[tokio::test]
+    async fn inline_stylesheet_links_skips_non_matching_tags_instead_of_stopping() {
+        // A non-stylesheet <link> (favicon) followed by a stylesheet <link>
+        // pointing at a closed local port: the loop must advance past the
+        // favicon tag and try to fetch the second one, which fails, rather
+        // than bailing out after the favicon tag.
+        let mut html = r#"<head><link rel="icon" href="favicon.ico"><link rel="stylesheet" href="http://127.0.0.1:1/style.css"></head>"#.to_string();
+        let err = inline_stylesheet_links(&mut html).await.unwrap_err();
+        assert!(err.contains("failed to fetch stylesheet http://127.0.0.1:1/style.css"));
+    }
+}
+
 fn main() {
   tauri::Builder::default()
     .plugin(tauri_plugin_dialog::init())
-    .invoke_handler(tauri::generate_handler![generate_site, save_zip])
+    .manage(PreviewState(std::sync::Mutex::new(None)))
+    .register_uri_scheme_protocol("slate", |app, request| preview_protocol_handler(app, request))
+    .invoke_handler(tauri::generate_handler![generate_site, save_zip, preview_site, export_single_file])
     .run(tauri::generate_context!())
     .expect("error while running tauri app");
 }